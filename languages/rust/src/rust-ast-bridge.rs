@@ -10,12 +10,14 @@ use std::path::Path;
 // For full AST support, compile with Cargo and syn crate
 
 #[cfg(feature = "syn")]
-use syn::{parse_file, Item, ItemFn, ItemStruct, ItemEnum, ItemTrait, ItemImpl, ItemMod, ItemUse, ItemConst, ItemType};
+use syn::{parse_file, Item};
 
-#[cfg(feature = "syn")]
-use serde_json::json;
+// Bumped whenever the serialized shape changes so consumers can branch on it,
+// mirroring how syn-serde versions its serialized AST.
+const SCHEMA_VERSION: u32 = 1;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct RustMetadata {
     structs: Vec<StructInfo>,
     enums: Vec<EnumInfo>,
@@ -26,84 +28,259 @@ struct RustMetadata {
     uses: Vec<String>,
     constants: Vec<ConstantInfo>,
     types: Vec<TypeInfo>,
+    // Trait name -> the types that implement it; inherent impls are collected
+    // under the synthetic `INHERENT_IMPL_KEY`. Built in a post-parse pass.
+    #[cfg_attr(feature = "syn", serde(rename = "traitIndex"))]
+    trait_index: std::collections::BTreeMap<String, Vec<String>>,
 }
 
+// Synthetic trait-index key grouping inherent (`impl Type`) blocks.
+const INHERENT_IMPL_KEY: &str = "<inherent>";
+
+// Mirrors the rustdoc-JSON visibility model rather than a bare boolean, so
+// discovery can tell `pub`, `pub(crate)`, and `pub(in path)` apart.
 #[derive(Debug)]
+enum Visibility {
+    Public,
+    Crate,
+    Restricted(String),
+    Private,
+}
+
+// Serialized as its rustdoc-style token string (e.g. `"public"`,
+// `"restricted(super)"`).
+#[cfg(feature = "syn")]
+impl serde::Serialize for Visibility {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.token())
+    }
+}
+
+impl Visibility {
+    #[cfg(feature = "syn")]
+    fn from_syn(vis: &syn::Visibility) -> Self {
+        match vis {
+            syn::Visibility::Public(_) => Visibility::Public,
+            syn::Visibility::Crate(_) => Visibility::Crate,
+            syn::Visibility::Restricted(restricted) => {
+                let path = restricted.path.segments.iter()
+                    .map(|s| s.ident.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                // `pub(crate)` parses as a restricted path of `crate`; treat it
+                // as the dedicated crate-visibility variant.
+                if restricted.in_token.is_none() && path == "crate" {
+                    Visibility::Crate
+                } else {
+                    Visibility::Restricted(path)
+                }
+            }
+            syn::Visibility::Inherited => Visibility::Private,
+        }
+    }
+
+    // Coarse mapping for the fallback parsers, which only see a leading `pub`.
+    #[cfg(not(feature = "syn"))]
+    fn from_pub(is_pub: bool) -> Self {
+        if is_pub {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+
+    // Serialized token used under the `"visibility"` key.
+    fn token(&self) -> String {
+        match self {
+            Visibility::Public => "public".to_string(),
+            Visibility::Crate => "crate".to_string(),
+            Visibility::Restricted(path) => format!("restricted({})", path),
+            Visibility::Private => "private".to_string(),
+        }
+    }
+}
+
+// Full generic-parameter list: one record per parameter plus the rendered
+// where-clause, so candidates that differ only in their bounds are
+// distinguishable during discovery.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
+struct GenericsInfo {
+    params: Vec<GenericParamInfo>,
+    #[cfg_attr(feature = "syn", serde(rename = "whereClause", skip_serializing_if = "Option::is_none"))]
+    where_clause: Option<String>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
+struct GenericParamInfo {
+    // `type`, `lifetime`, or `const`.
+    kind: String,
+    name: String,
+    // Inline bounds rendered via `quote`; for a const parameter this carries
+    // the parameter's type.
+    bounds: Vec<String>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    default: Option<String>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct StructInfo {
     name: String,
-    is_pub: bool,
-    generics: Vec<String>,
+    path: String,
+    visibility: Visibility,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
+    generics: GenericsInfo,
     fields: Vec<FieldInfo>,
     derives: Vec<String>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    cfg: Option<String>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct FieldInfo {
     name: String,
+    #[cfg_attr(feature = "syn", serde(rename = "type"))]
     ty: String,
-    is_pub: bool,
+    visibility: Visibility,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct EnumInfo {
     name: String,
-    is_pub: bool,
-    generics: Vec<String>,
+    path: String,
+    visibility: Visibility,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
+    generics: GenericsInfo,
     variants: Vec<String>,
     derives: Vec<String>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    cfg: Option<String>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct TraitInfo {
     name: String,
-    is_pub: bool,
-    generics: Vec<String>,
+    path: String,
+    visibility: Visibility,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
+    generics: GenericsInfo,
+    // Supertrait bounds declared in the trait's `:` bound list.
+    supertraits: Vec<String>,
     methods: Vec<String>,
+    #[cfg_attr(feature = "syn", serde(rename = "assocTypes"))]
+    assoc_types: Vec<String>,
+    #[cfg_attr(feature = "syn", serde(rename = "assocConsts"))]
+    assoc_consts: Vec<String>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    cfg: Option<String>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct FunctionInfo {
     name: String,
-    is_pub: bool,
+    path: String,
+    visibility: Visibility,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
+    #[cfg_attr(feature = "syn", serde(rename = "isAsync"))]
     is_async: bool,
+    #[cfg_attr(feature = "syn", serde(rename = "isConst"))]
     is_const: bool,
+    #[cfg_attr(feature = "syn", serde(rename = "isUnsafe"))]
     is_unsafe: bool,
-    generics: Vec<String>,
+    generics: GenericsInfo,
+    #[cfg_attr(feature = "syn", serde(rename = "parameters"))]
     params: Vec<ParamInfo>,
+    #[cfg_attr(feature = "syn", serde(rename = "returnType", skip_serializing_if = "Option::is_none"))]
     return_type: Option<String>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    cfg: Option<String>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct ParamInfo {
     name: String,
+    #[cfg_attr(feature = "syn", serde(rename = "type"))]
     ty: String,
+    #[cfg_attr(feature = "syn", serde(rename = "isMut"))]
     is_mut: bool,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct ImplInfo {
+    #[cfg_attr(feature = "syn", serde(rename = "traitName", skip_serializing_if = "Option::is_none"))]
     trait_name: Option<String>,
+    #[cfg_attr(feature = "syn", serde(rename = "targetType"))]
     target_type: String,
+    path: String,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
     methods: Vec<String>,
+    // `type Item = Foo;` bindings, so discovery can resolve `<T as Trait>::Item`.
+    #[cfg_attr(feature = "syn", serde(rename = "assocTypes"))]
+    assoc_types: Vec<AssocType>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    cfg: Option<String>,
 }
 
+// A single associated-type binding from an impl block.
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
+struct AssocType {
+    name: String,
+    #[cfg_attr(feature = "syn", serde(rename = "type"))]
+    ty: String,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct ModuleInfo {
     name: String,
-    is_pub: bool,
+    path: String,
+    visibility: Visibility,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    cfg: Option<String>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct ConstantInfo {
     name: String,
-    is_pub: bool,
+    path: String,
+    visibility: Visibility,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
+    #[cfg_attr(feature = "syn", serde(rename = "type", skip_serializing_if = "Option::is_none"))]
     ty: Option<String>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    cfg: Option<String>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "syn", derive(serde::Serialize))]
 struct TypeInfo {
     name: String,
-    is_pub: bool,
+    path: String,
+    visibility: Visibility,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    docs: Option<String>,
+    #[cfg_attr(feature = "syn", serde(skip_serializing_if = "Option::is_none"))]
+    cfg: Option<String>,
 }
 
 impl RustMetadata {
@@ -118,26 +295,90 @@ impl RustMetadata {
             uses: Vec::new(),
             constants: Vec::new(),
             types: Vec::new(),
+            trait_index: std::collections::BTreeMap::new(),
+        }
+    }
+
+    // Connect the flat `impls`/`traits` lists: record, for every trait, the
+    // types that implement it (inherent impls under `INHERENT_IMPL_KEY`), so
+    // consumers can ask "what implements `Serialize`?" without rescanning.
+    fn build_trait_index(&mut self) {
+        for imp in &self.impls {
+            let key = imp.trait_name.clone()
+                .unwrap_or_else(|| INHERENT_IMPL_KEY.to_string());
+            self.trait_index.entry(key)
+                .or_default()
+                .push(imp.target_type.clone());
         }
     }
 
+    #[cfg(feature = "syn")]
+    fn to_json(&self) -> String {
+        // serde handles all escaping, so type/path strings containing quotes,
+        // backslashes, or `<...>` no longer corrupt the output.
+        #[derive(serde::Serialize)]
+        struct Envelope<'a> {
+            #[serde(flatten)]
+            metadata: &'a RustMetadata,
+            #[serde(rename = "schemaVersion")]
+            schema_version: u32,
+            parser: &'static str,
+            version: &'static str,
+            success: bool,
+        }
+
+        let envelope = Envelope {
+            metadata: self,
+            schema_version: SCHEMA_VERSION,
+            parser: "rustc/syn",
+            version: "1.0.0",
+            success: true,
+        };
+
+        serde_json::to_string(&envelope)
+            .unwrap_or_else(|_| String::from("{\"success\":false}"))
+    }
+
+    // Standalone builds without the `syn` feature have no serde dependency and
+    // fall back to hand-rolled serialization of the best-effort metadata.
+    #[cfg(not(feature = "syn"))]
     fn to_json(&self) -> String {
-        // Manual JSON serialization for standalone compilation
         let mut json = String::from("{");
 
+        // Render the optional `"docs":"..."` member (with a leading comma) for an
+        // item, escaping the characters JSON requires.
+        fn docs_member(docs: &Option<String>) -> String {
+            match docs {
+                Some(text) => {
+                    let mut escaped = String::with_capacity(text.len());
+                    for c in text.chars() {
+                        match c {
+                            '\\' => escaped.push_str("\\\\"),
+                            '"' => escaped.push_str("\\\""),
+                            '\n' => escaped.push_str("\\n"),
+                            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                            c => escaped.push(c),
+                        }
+                    }
+                    format!(",\"docs\":\"{}\"", escaped)
+                }
+                None => String::new(),
+            }
+        }
+
         // Structs
         json.push_str("\"structs\":[");
         for (i, s) in self.structs.iter().enumerate() {
             if i > 0 { json.push_str(","); }
             json.push_str(&format!(
-                "{{\"name\":\"{}\",\"isPublic\":{},\"fields\":[",
-                s.name, s.is_pub
+                "{{\"name\":\"{}\",\"path\":\"{}\",\"visibility\":\"{}\"{},\"fields\":[",
+                s.name, s.path, s.visibility.token(), docs_member(&s.docs)
             ));
             for (j, f) in s.fields.iter().enumerate() {
                 if j > 0 { json.push_str(","); }
                 json.push_str(&format!(
-                    "{{\"name\":\"{}\",\"type\":\"{}\",\"isPublic\":{}}}",
-                    f.name, f.ty, f.is_pub
+                    "{{\"name\":\"{}\",\"type\":\"{}\",\"visibility\":\"{}\"{}}}",
+                    f.name, f.ty, f.visibility.token(), docs_member(&f.docs)
                 ));
             }
             json.push_str("]}");
@@ -149,8 +390,8 @@ impl RustMetadata {
         for (i, e) in self.enums.iter().enumerate() {
             if i > 0 { json.push_str(","); }
             json.push_str(&format!(
-                "{{\"name\":\"{}\",\"isPublic\":{},\"variants\":[",
-                e.name, e.is_pub
+                "{{\"name\":\"{}\",\"path\":\"{}\",\"visibility\":\"{}\"{},\"variants\":[",
+                e.name, e.path, e.visibility.token(), docs_member(&e.docs)
             ));
             for (j, v) in e.variants.iter().enumerate() {
                 if j > 0 { json.push_str(","); }
@@ -165,13 +406,28 @@ impl RustMetadata {
         for (i, t) in self.traits.iter().enumerate() {
             if i > 0 { json.push_str(","); }
             json.push_str(&format!(
-                "{{\"name\":\"{}\",\"isPublic\":{},\"methods\":[",
-                t.name, t.is_pub
+                "{{\"name\":\"{}\",\"path\":\"{}\",\"visibility\":\"{}\"{},\"methods\":[",
+                t.name, t.path, t.visibility.token(), docs_member(&t.docs)
             ));
             for (j, m) in t.methods.iter().enumerate() {
                 if j > 0 { json.push_str(","); }
                 json.push_str(&format!("\"{}\"", m));
             }
+            json.push_str("],\"supertraits\":[");
+            for (j, s) in t.supertraits.iter().enumerate() {
+                if j > 0 { json.push_str(","); }
+                json.push_str(&format!("\"{}\"", s));
+            }
+            json.push_str("],\"assocTypes\":[");
+            for (j, a) in t.assoc_types.iter().enumerate() {
+                if j > 0 { json.push_str(","); }
+                json.push_str(&format!("\"{}\"", a));
+            }
+            json.push_str("],\"assocConsts\":[");
+            for (j, a) in t.assoc_consts.iter().enumerate() {
+                if j > 0 { json.push_str(","); }
+                json.push_str(&format!("\"{}\"", a));
+            }
             json.push_str("]}");
         }
         json.push_str("],");
@@ -181,8 +437,8 @@ impl RustMetadata {
         for (i, f) in self.functions.iter().enumerate() {
             if i > 0 { json.push_str(","); }
             json.push_str(&format!(
-                "{{\"name\":\"{}\",\"isPublic\":{},\"isAsync\":{},\"isConst\":{},\"isUnsafe\":{},\"parameters\":[",
-                f.name, f.is_pub, f.is_async, f.is_const, f.is_unsafe
+                "{{\"name\":\"{}\",\"path\":\"{}\",\"visibility\":\"{}\"{},\"isAsync\":{},\"isConst\":{},\"isUnsafe\":{},\"parameters\":[",
+                f.name, f.path, f.visibility.token(), docs_member(&f.docs), f.is_async, f.is_const, f.is_unsafe
             ));
             for (j, p) in f.params.iter().enumerate() {
                 if j > 0 { json.push_str(","); }
@@ -203,15 +459,21 @@ impl RustMetadata {
         json.push_str("\"impls\":[");
         for (i, imp) in self.impls.iter().enumerate() {
             if i > 0 { json.push_str(","); }
-            json.push_str(&format!("{{\"targetType\":\"{}\"", imp.target_type));
+            json.push_str(&format!("{{\"targetType\":\"{}\",\"path\":\"{}\"", imp.target_type, imp.path));
             if let Some(ref trait_name) = imp.trait_name {
                 json.push_str(&format!(",\"traitName\":\"{}\"", trait_name));
             }
+            json.push_str(&docs_member(&imp.docs));
             json.push_str(",\"methods\":[");
             for (j, m) in imp.methods.iter().enumerate() {
                 if j > 0 { json.push_str(","); }
                 json.push_str(&format!("\"{}\"", m));
             }
+            json.push_str("],\"assocTypes\":[");
+            for (j, a) in imp.assoc_types.iter().enumerate() {
+                if j > 0 { json.push_str(","); }
+                json.push_str(&format!("{{\"name\":\"{}\",\"type\":\"{}\"}}", a.name, a.ty));
+            }
             json.push_str("]}");
         }
         json.push_str("],");
@@ -221,8 +483,8 @@ impl RustMetadata {
         for (i, m) in self.modules.iter().enumerate() {
             if i > 0 { json.push_str(","); }
             json.push_str(&format!(
-                "{{\"name\":\"{}\",\"isPublic\":{}}}",
-                m.name, m.is_pub
+                "{{\"name\":\"{}\",\"path\":\"{}\",\"visibility\":\"{}\"{}}}",
+                m.name, m.path, m.visibility.token(), docs_member(&m.docs)
             ));
         }
         json.push_str("],");
@@ -240,8 +502,8 @@ impl RustMetadata {
         for (i, c) in self.constants.iter().enumerate() {
             if i > 0 { json.push_str(","); }
             json.push_str(&format!(
-                "{{\"name\":\"{}\",\"isPublic\":{}",
-                c.name, c.is_pub
+                "{{\"name\":\"{}\",\"path\":\"{}\",\"visibility\":\"{}\"{}",
+                c.name, c.path, c.visibility.token(), docs_member(&c.docs)
             ));
             if let Some(ref ty) = c.ty {
                 json.push_str(&format!(",\"type\":\"{}\"", ty));
@@ -255,13 +517,27 @@ impl RustMetadata {
         for (i, t) in self.types.iter().enumerate() {
             if i > 0 { json.push_str(","); }
             json.push_str(&format!(
-                "{{\"name\":\"{}\",\"isPublic\":{}}}",
-                t.name, t.is_pub
+                "{{\"name\":\"{}\",\"path\":\"{}\",\"visibility\":\"{}\"{}}}",
+                t.name, t.path, t.visibility.token(), docs_member(&t.docs)
             ));
         }
         json.push_str("],");
 
+        // Trait index
+        json.push_str("\"traitIndex\":{");
+        for (i, (trait_name, targets)) in self.trait_index.iter().enumerate() {
+            if i > 0 { json.push_str(","); }
+            json.push_str(&format!("\"{}\":[", trait_name));
+            for (j, target) in targets.iter().enumerate() {
+                if j > 0 { json.push_str(","); }
+                json.push_str(&format!("\"{}\"", target));
+            }
+            json.push_str("]");
+        }
+        json.push_str("},");
+
         // Parser info
+        json.push_str(&format!("\"schemaVersion\":{},", SCHEMA_VERSION));
         json.push_str("\"parser\":\"rustc/syn\",");
         json.push_str("\"version\":\"1.0.0\",");
         json.push_str("\"success\":true");
@@ -272,41 +548,122 @@ impl RustMetadata {
 }
 
 #[cfg(feature = "syn")]
-fn parse_with_syn(file_path: &str) -> Result<RustMetadata, Box<dyn std::error::Error>> {
+fn parse_with_syn(file_path: &str, filter: Option<&CfgOptions>) -> Result<RustMetadata, Box<dyn std::error::Error>> {
+    let mut metadata = RustMetadata::new();
+    let mut stack: Vec<String> = vec!["crate".to_string()];
+    let mut visited: std::collections::HashSet<std::path::PathBuf> = std::collections::HashSet::new();
+    walk_file(Path::new(file_path), &mut metadata, &mut stack, &mut visited, filter)?;
+    metadata.build_trait_index();
+    Ok(metadata)
+}
+
+// Dotted, fully-qualified path for an item given the current module stack.
+#[cfg(feature = "syn")]
+fn qualify(stack: &[String], name: &str) -> String {
+    format!("{}.{}", stack.join("."), name)
+}
+
+// Parse one file and merge its items into `metadata`, following external
+// `mod foo;` declarations into the files they resolve to. The module
+// stack yields a dotted path (e.g. `crate.net.client.Connection`) for every
+// emitted item; the visited set of canonicalized paths guards against cycles.
+#[cfg(feature = "syn")]
+fn walk_file(
+    file_path: &Path,
+    metadata: &mut RustMetadata,
+    stack: &mut Vec<String>,
+    visited: &mut std::collections::HashSet<std::path::PathBuf>,
+    filter: Option<&CfgOptions>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let canonical = fs::canonicalize(file_path).unwrap_or_else(|_| file_path.to_path_buf());
+    if !visited.insert(canonical) {
+        return Ok(());
+    }
+
     let content = fs::read_to_string(file_path)?;
     let syntax = parse_file(&content)?;
+    let parent = file_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| std::path::PathBuf::from("."));
+    // rustc resolves submodules of a file module `foo.rs` under `foo/`, but
+    // submodules of `mod.rs` and the crate root (`lib.rs`/`main.rs`) under the
+    // file's own directory. Descend accordingly so `mod client;` inside
+    // `net.rs` is looked up at `net/client.rs`.
+    let stem = file_path.file_stem().and_then(|s| s.to_str());
+    let dir = match stem {
+        Some("mod") | Some("lib") | Some("main") | None => parent,
+        Some(stem) => parent.join(stem),
+    };
 
-    let mut metadata = RustMetadata::new();
+    walk_items(&syntax.items, metadata, stack, &dir, visited, filter)
+}
 
-    for item in syntax.items {
+// Recurse into a list of items under the current module stack. Inline modules
+// recurse in place; external module declarations are resolved to a file and
+// handed back to `walk_file`.
+#[cfg(feature = "syn")]
+fn walk_items(
+    items: &[Item],
+    metadata: &mut RustMetadata,
+    stack: &mut Vec<String>,
+    dir: &Path,
+    visited: &mut std::collections::HashSet<std::path::PathBuf>,
+    filter: Option<&CfgOptions>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for item in items {
         match item {
             Item::Struct(item_struct) => {
-                let struct_info = StructInfo {
-                    name: item_struct.ident.to_string(),
-                    is_pub: matches!(item_struct.vis, syn::Visibility::Public(_)),
+                let cfg = match cfg_gate(&item_struct.attrs, filter) {
+                    Some(cfg) => cfg,
+                    None => continue,
+                };
+                let name = item_struct.ident.to_string();
+                metadata.structs.push(StructInfo {
+                    path: qualify(stack, &name),
+                    name,
+                    visibility: Visibility::from_syn(&item_struct.vis),
+                    docs: extract_docs(&item_struct.attrs),
                     generics: extract_generics(&item_struct.generics),
                     fields: extract_fields(&item_struct.fields),
-                    derives: extract_derives(&item_struct.attrs),
-                };
-                metadata.structs.push(struct_info);
+                    derives: extract_derives(&item_struct.attrs, filter),
+                    cfg,
+                });
             }
             Item::Enum(item_enum) => {
-                let enum_info = EnumInfo {
-                    name: item_enum.ident.to_string(),
-                    is_pub: matches!(item_enum.vis, syn::Visibility::Public(_)),
+                let cfg = match cfg_gate(&item_enum.attrs, filter) {
+                    Some(cfg) => cfg,
+                    None => continue,
+                };
+                let name = item_enum.ident.to_string();
+                metadata.enums.push(EnumInfo {
+                    path: qualify(stack, &name),
+                    name,
+                    visibility: Visibility::from_syn(&item_enum.vis),
+                    docs: extract_docs(&item_enum.attrs),
                     generics: extract_generics(&item_enum.generics),
                     variants: item_enum.variants.iter()
                         .map(|v| v.ident.to_string())
                         .collect(),
-                    derives: extract_derives(&item_enum.attrs),
-                };
-                metadata.enums.push(enum_info);
+                    derives: extract_derives(&item_enum.attrs, filter),
+                    cfg,
+                });
             }
             Item::Trait(item_trait) => {
-                let trait_info = TraitInfo {
-                    name: item_trait.ident.to_string(),
-                    is_pub: matches!(item_trait.vis, syn::Visibility::Public(_)),
+                let cfg = match cfg_gate(&item_trait.attrs, filter) {
+                    Some(cfg) => cfg,
+                    None => continue,
+                };
+                let name = item_trait.ident.to_string();
+                metadata.traits.push(TraitInfo {
+                    path: qualify(stack, &name),
+                    name,
+                    visibility: Visibility::from_syn(&item_trait.vis),
+                    docs: extract_docs(&item_trait.attrs),
                     generics: extract_generics(&item_trait.generics),
+                    supertraits: item_trait.supertraits.iter()
+                        .map(|bound| quote::quote!(#bound).to_string())
+                        .collect(),
                     methods: item_trait.items.iter()
                         .filter_map(|item| {
                             if let syn::TraitItem::Method(method) = item {
@@ -316,30 +673,62 @@ fn parse_with_syn(file_path: &str) -> Result<RustMetadata, Box<dyn std::error::E
                             }
                         })
                         .collect(),
-                };
-                metadata.traits.push(trait_info);
+                    assoc_types: item_trait.items.iter()
+                        .filter_map(|item| {
+                            if let syn::TraitItem::Type(assoc) = item {
+                                Some(assoc.ident.to_string())
+                            } else {
+                                None
+                            }
+                        })
+                        .collect(),
+                    assoc_consts: item_trait.items.iter()
+                        .filter_map(|item| {
+                            if let syn::TraitItem::Const(assoc) = item {
+                                Some(assoc.ident.to_string())
+                            } else {
+                                None
+                            }
+                        })
+                        .collect(),
+                    cfg,
+                });
             }
             Item::Fn(item_fn) => {
-                let func_info = FunctionInfo {
-                    name: item_fn.sig.ident.to_string(),
-                    is_pub: matches!(item_fn.vis, syn::Visibility::Public(_)),
+                let cfg = match cfg_gate(&item_fn.attrs, filter) {
+                    Some(cfg) => cfg,
+                    None => continue,
+                };
+                let name = item_fn.sig.ident.to_string();
+                metadata.functions.push(FunctionInfo {
+                    path: qualify(stack, &name),
+                    name,
+                    visibility: Visibility::from_syn(&item_fn.vis),
+                    docs: extract_docs(&item_fn.attrs),
                     is_async: item_fn.sig.asyncness.is_some(),
                     is_const: item_fn.sig.constness.is_some(),
                     is_unsafe: item_fn.sig.unsafety.is_some(),
                     generics: extract_generics(&item_fn.sig.generics),
                     params: extract_params(&item_fn.sig.inputs),
                     return_type: extract_return_type(&item_fn.sig.output),
-                };
-                metadata.functions.push(func_info);
+                    cfg,
+                });
             }
             Item::Impl(item_impl) => {
-                let impl_info = ImplInfo {
+                let cfg = match cfg_gate(&item_impl.attrs, filter) {
+                    Some(cfg) => cfg,
+                    None => continue,
+                };
+                let target_type = extract_type(&item_impl.self_ty);
+                metadata.impls.push(ImplInfo {
                     trait_name: item_impl.trait_.as_ref().map(|(_, path, _)| {
                         path.segments.last()
                             .map(|s| s.ident.to_string())
                             .unwrap_or_default()
                     }),
-                    target_type: extract_type(&item_impl.self_ty),
+                    path: qualify(stack, &target_type),
+                    target_type,
+                    docs: extract_docs(&item_impl.attrs),
                     methods: item_impl.items.iter()
                         .filter_map(|item| {
                             if let syn::ImplItem::Method(method) = item {
@@ -349,15 +738,51 @@ fn parse_with_syn(file_path: &str) -> Result<RustMetadata, Box<dyn std::error::E
                             }
                         })
                         .collect(),
-                };
-                metadata.impls.push(impl_info);
+                    assoc_types: item_impl.items.iter()
+                        .filter_map(|item| {
+                            if let syn::ImplItem::Type(assoc) = item {
+                                let ty = &assoc.ty;
+                                Some(AssocType {
+                                    name: assoc.ident.to_string(),
+                                    ty: quote::quote!(#ty).to_string(),
+                                })
+                            } else {
+                                None
+                            }
+                        })
+                        .collect(),
+                    cfg,
+                });
             }
             Item::Mod(item_mod) => {
-                let mod_info = ModuleInfo {
-                    name: item_mod.ident.to_string(),
-                    is_pub: matches!(item_mod.vis, syn::Visibility::Public(_)),
+                let cfg = match cfg_gate(&item_mod.attrs, filter) {
+                    Some(cfg) => cfg,
+                    None => continue,
                 };
-                metadata.modules.push(mod_info);
+                let name = item_mod.ident.to_string();
+                metadata.modules.push(ModuleInfo {
+                    path: qualify(stack, &name),
+                    name: name.clone(),
+                    visibility: Visibility::from_syn(&item_mod.vis),
+                    docs: extract_docs(&item_mod.attrs),
+                    cfg,
+                });
+                stack.push(name.clone());
+                match &item_mod.content {
+                    Some((_, inner)) => {
+                        // An inline `mod outer { ... }` opens a new module
+                        // directory, so an external submodule declared inside
+                        // it resolves under `<dir>/outer/`, as rustc does.
+                        let inner_dir = dir.join(&name);
+                        walk_items(inner, metadata, stack, &inner_dir, visited, filter)?;
+                    }
+                    None => {
+                        if let Some(resolved) = resolve_module_file(dir, &name, &item_mod.attrs) {
+                            walk_file(&resolved, metadata, stack, visited, filter)?;
+                        }
+                    }
+                }
+                stack.pop();
             }
             Item::Use(item_use) => {
                 if let Some(use_path) = extract_use_path(&item_use.tree) {
@@ -365,28 +790,253 @@ fn parse_with_syn(file_path: &str) -> Result<RustMetadata, Box<dyn std::error::E
                 }
             }
             Item::Const(item_const) => {
-                let const_info = ConstantInfo {
-                    name: item_const.ident.to_string(),
-                    is_pub: matches!(item_const.vis, syn::Visibility::Public(_)),
-                    ty: Some(extract_type(&item_const.ty)),
+                let cfg = match cfg_gate(&item_const.attrs, filter) {
+                    Some(cfg) => cfg,
+                    None => continue,
                 };
-                metadata.constants.push(const_info);
+                let name = item_const.ident.to_string();
+                metadata.constants.push(ConstantInfo {
+                    path: qualify(stack, &name),
+                    name,
+                    visibility: Visibility::from_syn(&item_const.vis),
+                    docs: extract_docs(&item_const.attrs),
+                    ty: Some(extract_type(&item_const.ty)),
+                    cfg,
+                });
             }
             Item::Type(item_type) => {
-                let type_info = TypeInfo {
-                    name: item_type.ident.to_string(),
-                    is_pub: matches!(item_type.vis, syn::Visibility::Public(_)),
+                let cfg = match cfg_gate(&item_type.attrs, filter) {
+                    Some(cfg) => cfg,
+                    None => continue,
                 };
-                metadata.types.push(type_info);
+                let name = item_type.ident.to_string();
+                metadata.types.push(TypeInfo {
+                    path: qualify(stack, &name),
+                    name,
+                    visibility: Visibility::from_syn(&item_type.vis),
+                    docs: extract_docs(&item_type.attrs),
+                    cfg,
+                });
             }
             _ => {}
         }
     }
 
-    Ok(metadata)
+    Ok(())
+}
+
+// Resolve an external `mod foo;` declaration to its file, honoring an explicit
+// `#[path = "..."]` override first and otherwise trying `<dir>/foo.rs` then
+// `<dir>/foo/mod.rs`, as rustc's module resolution does.
+#[cfg(feature = "syn")]
+fn resolve_module_file(dir: &Path, name: &str, attrs: &[syn::Attribute]) -> Option<std::path::PathBuf> {
+    for attr in attrs {
+        if attr.path.is_ident("path") {
+            if let Ok(syn::Meta::NameValue(nv)) = attr.parse_meta() {
+                if let syn::Lit::Str(lit) = nv.lit {
+                    return Some(dir.join(lit.value()));
+                }
+            }
+        }
+    }
+
+    let direct = dir.join(format!("{}.rs", name));
+    if direct.exists() {
+        return Some(direct);
+    }
+
+    let nested = dir.join(name).join("mod.rs");
+    if nested.exists() {
+        return Some(nested);
+    }
+
+    None
+}
+
+// The `--cfg`/`--features` configuration an item's predicate is evaluated
+// against. Unknown flags default to false, matching rustc.
+#[cfg(feature = "syn")]
+#[derive(Debug, Default)]
+struct CfgOptions {
+    features: std::collections::HashSet<String>,
+    flags: std::collections::HashSet<String>,
+    values: std::collections::HashSet<(String, String)>,
+}
+
+// A parsed `cfg` predicate tree supporting `all`/`any`/`not`, `feature = "x"`,
+// key/value pairs, and bare flags.
+#[cfg(feature = "syn")]
+enum CfgExpr {
+    All(Vec<CfgExpr>),
+    Any(Vec<CfgExpr>),
+    Not(Box<CfgExpr>),
+    Feature(String),
+    KeyValue(String, String),
+    Flag(String),
+}
+
+#[cfg(feature = "syn")]
+impl CfgExpr {
+    // Normalized rendering, e.g. `all(feature = "net", not(test))`.
+    fn render(&self) -> String {
+        match self {
+            CfgExpr::All(items) => format!("all({})", Self::render_list(items)),
+            CfgExpr::Any(items) => format!("any({})", Self::render_list(items)),
+            CfgExpr::Not(inner) => format!("not({})", inner.render()),
+            CfgExpr::Feature(name) => format!("feature = \"{}\"", name),
+            CfgExpr::KeyValue(key, value) => format!("{} = \"{}\"", key, value),
+            CfgExpr::Flag(name) => name.clone(),
+        }
+    }
+
+    fn render_list(items: &[CfgExpr]) -> String {
+        items.iter().map(CfgExpr::render).collect::<Vec<_>>().join(", ")
+    }
+
+    fn eval(&self, cfg: &CfgOptions) -> bool {
+        match self {
+            CfgExpr::All(items) => items.iter().all(|item| item.eval(cfg)),
+            CfgExpr::Any(items) => items.iter().any(|item| item.eval(cfg)),
+            CfgExpr::Not(inner) => !inner.eval(cfg),
+            CfgExpr::Feature(name) => cfg.features.contains(name),
+            CfgExpr::KeyValue(key, value) => cfg.values.contains(&(key.clone(), value.clone())),
+            CfgExpr::Flag(name) => cfg.flags.contains(name),
+        }
+    }
+}
+
+// Build a `CfgExpr` from a predicate `syn::Meta` node.
+#[cfg(feature = "syn")]
+fn parse_cfg_meta(meta: &syn::Meta) -> Option<CfgExpr> {
+    match meta {
+        syn::Meta::Path(path) => path.get_ident().map(|id| CfgExpr::Flag(id.to_string())),
+        syn::Meta::List(list) => {
+            let ident = list.path.get_ident()?.to_string();
+            let children: Vec<CfgExpr> = list.nested.iter()
+                .filter_map(|nested| {
+                    if let syn::NestedMeta::Meta(inner) = nested {
+                        parse_cfg_meta(inner)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            match ident.as_str() {
+                "all" => Some(CfgExpr::All(children)),
+                "any" => Some(CfgExpr::Any(children)),
+                "not" => children.into_iter().next().map(|c| CfgExpr::Not(Box::new(c))),
+                _ => None,
+            }
+        }
+        syn::Meta::NameValue(nv) => {
+            let key = nv.path.get_ident()?.to_string();
+            if let syn::Lit::Str(lit) = &nv.lit {
+                if key == "feature" {
+                    Some(CfgExpr::Feature(lit.value()))
+                } else {
+                    Some(CfgExpr::KeyValue(key, lit.value()))
+                }
+            } else {
+                None
+            }
+        }
+    }
+}
+
+// Extract the `#[cfg(...)]` predicate from an item's attributes, if any.
+#[cfg(feature = "syn")]
+fn extract_cfg(attrs: &[syn::Attribute]) -> Option<CfgExpr> {
+    // rustc ANDs multiple `#[cfg]` attributes on one item, so collect every
+    // predicate and combine them under an implicit `all(...)`.
+    let mut predicates: Vec<CfgExpr> = Vec::new();
+    for attr in attrs {
+        if attr.path.is_ident("cfg") {
+            if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
+                if let Some(syn::NestedMeta::Meta(inner)) = list.nested.first() {
+                    if let Some(expr) = parse_cfg_meta(inner) {
+                        predicates.push(expr);
+                    }
+                }
+            }
+        }
+    }
+    match predicates.len() {
+        0 => None,
+        1 => predicates.into_iter().next(),
+        _ => Some(CfgExpr::All(predicates)),
+    }
+}
+
+// Gate an item by its `cfg` predicate. Returns `None` when `filter` is active
+// and the predicate evaluates to false (the item should be dropped), otherwise
+// `Some(normalized_cfg)` for the `"cfg"` field.
+#[cfg(feature = "syn")]
+fn cfg_gate(attrs: &[syn::Attribute], filter: Option<&CfgOptions>) -> Option<Option<String>> {
+    let expr = extract_cfg(attrs);
+    if let (Some(options), Some(expr)) = (filter, expr.as_ref()) {
+        if !expr.eval(options) {
+            return None;
+        }
+    }
+    Some(expr.map(|e| e.render()))
+}
+
+// Parse `--cfg name=value` / `--features a,b` arguments into a `CfgOptions`.
+// Returns `None` when no such arguments are present, leaving filtering off.
+#[cfg(feature = "syn")]
+fn parse_cfg_args(args: &[String]) -> Option<CfgOptions> {
+    let mut options = CfgOptions::default();
+    let mut supplied = false;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--cfg" => {
+                if let Some(spec) = iter.next() {
+                    supplied = true;
+                    match spec.split_once('=') {
+                        Some(("feature", value)) => {
+                            options.features.insert(unquote(value));
+                        }
+                        Some((key, value)) => {
+                            options.values.insert((key.to_string(), unquote(value)));
+                        }
+                        None => {
+                            options.flags.insert(spec.clone());
+                        }
+                    }
+                }
+            }
+            "--features" => {
+                if let Some(list) = iter.next() {
+                    supplied = true;
+                    for feature in list.split(',').filter(|s| !s.is_empty()) {
+                        options.features.insert(feature.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if supplied {
+        Some(options)
+    } else {
+        None
+    }
+}
+
+// Strip matching surrounding quotes from a `--cfg key="value"` argument.
+#[cfg(feature = "syn")]
+fn unquote(value: &str) -> String {
+    value.strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
 }
 
 // Fallback parser using regex when syn is not available
+#[cfg(not(feature = "syn"))]
 fn parse_with_regex(file_path: &str) -> Result<RustMetadata, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(file_path)?;
     let mut metadata = RustMetadata::new();
@@ -394,50 +1044,69 @@ fn parse_with_regex(file_path: &str) -> Result<RustMetadata, Box<dyn std::error:
     // Parse structs
     let struct_re = regex::Regex::new(r"(?m)^\s*(pub\s+)?struct\s+(\w+)")?;
     for cap in struct_re.captures_iter(&content) {
+        let name = cap[2].to_string();
         metadata.structs.push(StructInfo {
-            name: cap[2].to_string(),
-            is_pub: cap.get(1).is_some(),
-            generics: Vec::new(),
+            path: format!("crate.{}", name),
+            name,
+            visibility: Visibility::from_pub(cap.get(1).is_some()),
+            docs: None,
+            generics: GenericsInfo::default(),
             fields: Vec::new(),
             derives: Vec::new(),
+            cfg: None,
         });
     }
 
     // Parse enums
     let enum_re = regex::Regex::new(r"(?m)^\s*(pub\s+)?enum\s+(\w+)")?;
     for cap in enum_re.captures_iter(&content) {
+        let name = cap[2].to_string();
         metadata.enums.push(EnumInfo {
-            name: cap[2].to_string(),
-            is_pub: cap.get(1).is_some(),
-            generics: Vec::new(),
+            path: format!("crate.{}", name),
+            name,
+            visibility: Visibility::from_pub(cap.get(1).is_some()),
+            docs: None,
+            generics: GenericsInfo::default(),
             variants: Vec::new(),
             derives: Vec::new(),
+            cfg: None,
         });
     }
 
     // Parse traits
     let trait_re = regex::Regex::new(r"(?m)^\s*(pub\s+)?trait\s+(\w+)")?;
     for cap in trait_re.captures_iter(&content) {
+        let name = cap[2].to_string();
         metadata.traits.push(TraitInfo {
-            name: cap[2].to_string(),
-            is_pub: cap.get(1).is_some(),
-            generics: Vec::new(),
+            path: format!("crate.{}", name),
+            name,
+            visibility: Visibility::from_pub(cap.get(1).is_some()),
+            docs: None,
+            generics: GenericsInfo::default(),
+            supertraits: Vec::new(),
             methods: Vec::new(),
+            assoc_types: Vec::new(),
+            assoc_consts: Vec::new(),
+            cfg: None,
         });
     }
 
     // Parse functions
     let fn_re = regex::Regex::new(r"(?m)^\s*(pub\s+)?(async\s+)?(const\s+)?(unsafe\s+)?fn\s+(\w+)")?;
     for cap in fn_re.captures_iter(&content) {
+        let name = cap[5].to_string();
         metadata.functions.push(FunctionInfo {
-            name: cap[5].to_string(),
-            is_pub: cap.get(1).is_some(),
+            path: format!("crate.{}", name),
+            name,
+            visibility: Visibility::from_pub(cap.get(1).is_some()),
+            docs: None,
             is_async: cap.get(2).is_some(),
             is_const: cap.get(3).is_some(),
             is_unsafe: cap.get(4).is_some(),
-            generics: Vec::new(),
+            generics: GenericsInfo::default(),
             params: Vec::new(),
             return_type: None,
+            cfg: None,
         });
     }
 
@@ -450,28 +1119,40 @@ fn parse_with_regex(file_path: &str) -> Result<RustMetadata, Box<dyn std::error:
     // Parse modules
     let mod_re = regex::Regex::new(r"(?m)^\s*(pub\s+)?mod\s+(\w+)")?;
     for cap in mod_re.captures_iter(&content) {
+        let name = cap[2].to_string();
         metadata.modules.push(ModuleInfo {
-            name: cap[2].to_string(),
-            is_pub: cap.get(1).is_some(),
+            path: format!("crate.{}", name),
+            name,
+            visibility: Visibility::from_pub(cap.get(1).is_some()),
+            docs: None,
+            cfg: None,
         });
     }
 
     // Parse constants
     let const_re = regex::Regex::new(r"(?m)^\s*(pub\s+)?const\s+(\w+)")?;
     for cap in const_re.captures_iter(&content) {
+        let name = cap[2].to_string();
         metadata.constants.push(ConstantInfo {
-            name: cap[2].to_string(),
-            is_pub: cap.get(1).is_some(),
+            path: format!("crate.{}", name),
+            name,
+            visibility: Visibility::from_pub(cap.get(1).is_some()),
+            docs: None,
             ty: None,
+            cfg: None,
         });
     }
 
     // Parse type aliases
     let type_re = regex::Regex::new(r"(?m)^\s*(pub\s+)?type\s+(\w+)")?;
     for cap in type_re.captures_iter(&content) {
+        let name = cap[2].to_string();
         metadata.types.push(TypeInfo {
-            name: cap[2].to_string(),
-            is_pub: cap.get(1).is_some(),
+            path: format!("crate.{}", name),
+            name,
+            visibility: Visibility::from_pub(cap.get(1).is_some()),
+            docs: None,
+            cfg: None,
         });
     }
 
@@ -480,16 +1161,48 @@ fn parse_with_regex(file_path: &str) -> Result<RustMetadata, Box<dyn std::error:
 
 // Helper functions for syn feature
 #[cfg(feature = "syn")]
-fn extract_generics(generics: &syn::Generics) -> Vec<String> {
-    generics.params.iter()
-        .filter_map(|param| {
-            if let syn::GenericParam::Type(type_param) = param {
-                Some(type_param.ident.to_string())
-            } else {
-                None
-            }
+fn extract_generics(generics: &syn::Generics) -> GenericsInfo {
+    let params = generics.params.iter()
+        .map(|param| match param {
+            syn::GenericParam::Type(type_param) => GenericParamInfo {
+                kind: "type".to_string(),
+                name: type_param.ident.to_string(),
+                bounds: type_param.bounds.iter()
+                    .map(|bound| quote::quote!(#bound).to_string())
+                    .collect(),
+                default: type_param.default.as_ref()
+                    .map(|ty| quote::quote!(#ty).to_string()),
+            },
+            syn::GenericParam::Lifetime(lifetime_def) => GenericParamInfo {
+                kind: "lifetime".to_string(),
+                name: format!("'{}", lifetime_def.lifetime.ident),
+                bounds: lifetime_def.bounds.iter()
+                    .map(|bound| format!("'{}", bound.ident))
+                    .collect(),
+                default: None,
+            },
+            syn::GenericParam::Const(const_param) => GenericParamInfo {
+                kind: "const".to_string(),
+                name: const_param.ident.to_string(),
+                // A const parameter has a type rather than trait bounds.
+                bounds: vec![{
+                    let ty = &const_param.ty;
+                    quote::quote!(#ty).to_string()
+                }],
+                default: const_param.default.as_ref()
+                    .map(|expr| quote::quote!(#expr).to_string()),
+            },
         })
-        .collect()
+        .collect();
+
+    let where_clause = generics.where_clause.as_ref().map(|clause| {
+        clause.predicates.iter()
+            .map(|predicate| quote::quote!(#predicate).to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+
+    GenericsInfo { params, where_clause }
 }
 
 #[cfg(feature = "syn")]
@@ -502,7 +1215,8 @@ fn extract_fields(fields: &syn::Fields) -> Vec<FieldInfo> {
                         .map(|i| i.to_string())
                         .unwrap_or_default(),
                     ty: extract_type(&field.ty),
-                    is_pub: matches!(field.vis, syn::Visibility::Public(_)),
+                    visibility: Visibility::from_syn(&field.vis),
+                    docs: extract_docs(&field.attrs),
                 })
                 .collect()
         }
@@ -512,7 +1226,8 @@ fn extract_fields(fields: &syn::Fields) -> Vec<FieldInfo> {
                 .map(|(i, field)| FieldInfo {
                     name: i.to_string(),
                     ty: extract_type(&field.ty),
-                    is_pub: matches!(field.vis, syn::Visibility::Public(_)),
+                    visibility: Visibility::from_syn(&field.vis),
+                    docs: extract_docs(&field.attrs),
                 })
                 .collect()
         }
@@ -525,26 +1240,82 @@ fn extract_type(ty: &syn::Type) -> String {
     quote::quote!(#ty).to_string()
 }
 
+// Concatenate an item's doc comments (`///`, `//!`, and explicit
+// `#[doc = "..."]`, all of which syn normalizes to `doc` name-value attrs),
+// trimming the single leading space rustdoc inserts for `///`-style comments.
+#[cfg(feature = "syn")]
+fn extract_docs(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs.iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| {
+            if let Ok(syn::Meta::NameValue(nv)) = attr.parse_meta() {
+                if let syn::Lit::Str(lit) = nv.lit {
+                    let value = lit.value();
+                    return Some(value.strip_prefix(' ').map(str::to_string).unwrap_or(value));
+                }
+            }
+            None
+        })
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+// Collect derive names from plain `#[derive(...)]` attributes as well as
+// `#[cfg_attr(cond, derive(...))]` ones. When `filter` is active, a
+// `cfg_attr` contributes its derives only if its condition holds.
 #[cfg(feature = "syn")]
-fn extract_derives(attrs: &[syn::Attribute]) -> Vec<String> {
-    attrs.iter()
-        .filter(|attr| attr.path.is_ident("derive"))
-        .flat_map(|attr| {
+fn extract_derives(attrs: &[syn::Attribute], filter: Option<&CfgOptions>) -> Vec<String> {
+    let mut derives = Vec::new();
+
+    for attr in attrs {
+        if attr.path.is_ident("derive") {
+            if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
+                collect_derive_paths(&list.nested, &mut derives);
+            }
+        } else if attr.path.is_ident("cfg_attr") {
             if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
-                list.nested.iter()
-                    .filter_map(|nested| {
-                        if let syn::NestedMeta::Meta(syn::Meta::Path(path)) = nested {
-                            path.get_ident().map(|i| i.to_string())
-                        } else {
-                            None
+                let mut nested = list.nested.iter();
+                let condition = nested.next();
+                let active = match (filter, condition) {
+                    (Some(options), Some(syn::NestedMeta::Meta(meta))) => {
+                        parse_cfg_meta(meta).map(|expr| expr.eval(options)).unwrap_or(true)
+                    }
+                    _ => true,
+                };
+                if !active {
+                    continue;
+                }
+                for applied in nested {
+                    if let syn::NestedMeta::Meta(syn::Meta::List(inner)) = applied {
+                        if inner.path.is_ident("derive") {
+                            collect_derive_paths(&inner.nested, &mut derives);
                         }
-                    })
-                    .collect()
-            } else {
-                Vec::new()
+                    }
+                }
             }
-        })
-        .collect()
+        }
+    }
+
+    derives
+}
+
+#[cfg(feature = "syn")]
+fn collect_derive_paths(
+    nested: &syn::punctuated::Punctuated<syn::NestedMeta, syn::token::Comma>,
+    out: &mut Vec<String>,
+) {
+    for item in nested {
+        if let syn::NestedMeta::Meta(syn::Meta::Path(path)) = item {
+            if let Some(ident) = path.get_ident() {
+                out.push(ident.to_string());
+            }
+        }
+    }
 }
 
 #[cfg(feature = "syn")]
@@ -613,7 +1384,11 @@ fn main() {
     let result = {
         #[cfg(feature = "syn")]
         {
-            parse_with_syn(file_path)
+            // An optional `--cfg name=value` / `--features a,b` set restricts the
+            // emitted items to those whose `cfg` predicate holds; absent any such
+            // argument, every item is emitted and only tagged with its predicate.
+            let filter = parse_cfg_args(&args[2..]);
+            parse_with_syn(file_path, filter.as_ref())
         }
         #[cfg(not(feature = "syn"))]
         {
@@ -632,11 +1407,14 @@ fn main() {
                         if let Some(name_end) = trimmed[name_start..].find(|c: char| !c.is_alphanumeric() && c != '_') {
                             let name = &trimmed[name_start..name_start + name_end];
                             metadata.structs.push(StructInfo {
+                                path: format!("crate.{}", name),
                                 name: name.to_string(),
-                                is_pub,
-                                generics: Vec::new(),
+                                visibility: Visibility::from_pub(is_pub),
+                                docs: None,
+                                generics: GenericsInfo::default(),
                                 fields: Vec::new(),
                                 derives: Vec::new(),
+                                cfg: None,
                             });
                         }
                     } else if trimmed.starts_with("pub enum ") || trimmed.starts_with("enum ") {
@@ -645,11 +1423,14 @@ fn main() {
                         if let Some(name_end) = trimmed[name_start..].find(|c: char| !c.is_alphanumeric() && c != '_') {
                             let name = &trimmed[name_start..name_start + name_end];
                             metadata.enums.push(EnumInfo {
+                                path: format!("crate.{}", name),
                                 name: name.to_string(),
-                                is_pub,
-                                generics: Vec::new(),
+                                visibility: Visibility::from_pub(is_pub),
+                                docs: None,
+                                generics: GenericsInfo::default(),
                                 variants: Vec::new(),
                                 derives: Vec::new(),
+                                cfg: None,
                             });
                         }
                     } else if trimmed.starts_with("pub fn ") || trimmed.starts_with("fn ") ||
@@ -661,14 +1442,17 @@ fn main() {
                         if let Some(name_end) = trimmed[name_start..].find(|c: char| c == '(' || c == '<') {
                             let name = &trimmed[name_start..name_start + name_end];
                             metadata.functions.push(FunctionInfo {
+                                path: format!("crate.{}", name),
                                 name: name.to_string(),
-                                is_pub,
+                                visibility: Visibility::from_pub(is_pub),
+                                docs: None,
                                 is_async,
                                 is_const: false,
                                 is_unsafe: trimmed.contains("unsafe "),
-                                generics: Vec::new(),
+                                generics: GenericsInfo::default(),
                                 params: Vec::new(),
                                 return_type: None,
+                                cfg: None,
                             });
                         }
                     }